@@ -0,0 +1,49 @@
+import { describe, it, expect, beforeEach, afterEach, vi } from 'vitest';
+import { handle } from '../hooks.server';
+
+const mockEnv = vi.hoisted(() => ({}) as Record<string, string | undefined>);
+
+vi.mock('$lib/db/client');
+vi.mock('$app/environment', () => ({
+	dev: false
+}));
+vi.mock('$env/dynamic/private', () => ({
+	env: mockEnv
+}));
+
+describe('handle', () => {
+	const resolve = vi.fn(async () => new Response('OK'));
+
+	// Build a minimal unauthenticated request event for a path
+	function eventFor(pathname: string) {
+		return {
+			url: new URL(pathname, 'http://localhost'),
+			cookies: { get: vi.fn(), set: vi.fn(), delete: vi.fn() },
+			locals: {}
+		} as any;
+	}
+
+	beforeEach(() => {
+		vi.clearAllMocks();
+	});
+
+	afterEach(() => {
+		delete mockEnv.ENABLE_MAGIC_LINK;
+	});
+
+	it('should return 404 for routes of a disabled sign-in method', async () => {
+		mockEnv.ENABLE_MAGIC_LINK = 'false';
+
+		const response = await handle({ event: eventFor('/api/sign-in/magic-link'), resolve });
+
+		expect(response.status).toBe(404);
+		expect(resolve).not.toHaveBeenCalled();
+	});
+
+	it('should serve routes of an enabled sign-in method', async () => {
+		const response = await handle({ event: eventFor('/api/sign-in/magic-link'), resolve });
+
+		expect(response.status).toBe(200);
+		expect(resolve).toHaveBeenCalled();
+	});
+});
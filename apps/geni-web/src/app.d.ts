@@ -1,18 +1,12 @@
 // See https://svelte.dev/docs/kit/types#app.d.ts
 // for information about these interfaces
+import type { SessionUser } from '$lib/auth/services/session-user';
+
 declare global {
 	namespace App {
 		// interface Error {}
 		interface Locals {
-			user?: {
-				id: string;
-				email?: string;
-				phone?: string;
-				name: string;
-				plan: string;
-				created_at: string;
-				onboarding_completed: boolean;
-			};
+			user?: SessionUser;
 		}
 		// interface PageData {}
 		// interface PageState {}
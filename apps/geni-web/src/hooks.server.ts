@@ -1,14 +1,22 @@
 import { authenticateRequest } from '$lib/auth/services/jwt.server';
-import { clearAuthCookies } from '$lib/auth/services/cookies';
+import {
+	assertCookiePrefixConstraints,
+	clearAuthCookies,
+	getCookieSecurityWarning
+} from '$lib/auth/services/cookies.server';
+import { getFeatureFlags, isRouteDisabled } from '$lib/auth/services/features.server';
+import { toSessionUser } from '$lib/auth/services/session-user';
 import { db } from '$lib/db/client';
 import { users } from '$lib/db/schemas/users';
 import { eq } from 'drizzle-orm';
-import type { Handle } from '@sveltejs/kit';
+import { env } from '$env/dynamic/private';
+import type { Handle, ServerInit } from '@sveltejs/kit';
 
 // Public routes that don't require authentication
 const PUBLIC_ROUTES = [
 	'/',
 	'/analyze',
+	'/api/auth/config',
 	'/api/sign-in/magic-link',
 	'/api/sign-in/magic-link/verify',
 	'/api/sign-in/phone-otp',
@@ -37,10 +45,26 @@ function isProtectedRoute(pathname: string): boolean {
 	return PROTECTED_ROUTES.some((route) => pathname.startsWith(route));
 }
 
+export const init: ServerInit = () => {
+	// Refuse to start with a cookie prefix the configured attributes would violate
+	assertCookiePrefixConstraints();
+
+	// Warn loudly about cookie settings that would make sign-in silently fail
+	const warning = getCookieSecurityWarning(env.ORIGIN);
+	if (warning) {
+		console.warn(warning);
+	}
+};
+
 export const handle: Handle = async ({ event, resolve }) => {
 	const { cookies, url } = event;
 	const pathname = url.pathname;
 
+	// Hide routes of sign-in methods disabled for this deployment
+	if (isRouteDisabled(pathname, getFeatureFlags())) {
+		return new Response('Not Found', { status: 404 });
+	}
+
 	// Verify JWT authentication from cookies
 	const authResult = authenticateRequest(cookies);
 
@@ -52,8 +76,8 @@ export const handle: Handle = async ({ event, resolve }) => {
 			});
 
 			if (dbUser) {
-				// Set user in locals with database user data
-				event.locals.user = dbUser as any;
+				// Set user in locals with only the fields safe to expose
+				event.locals.user = toSessionUser(dbUser);
 
 				// If authenticated user tries to access sign-in page, redirect them
 				if (pathname === '/sign-in') {
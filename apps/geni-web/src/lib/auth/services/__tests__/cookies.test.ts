@@ -1,18 +1,27 @@
-import { describe, it, expect, beforeEach, vi } from 'vitest';
+import { describe, it, expect, beforeEach, afterEach, vi } from 'vitest';
 import {
 	setAuthCookie,
 	clearAuthCookies,
 	getAuthToken,
 	isAuthenticated,
+	getCookieSecurityWarning,
+	assertCookiePrefixConstraints,
 	COOKIE_OPTIONS,
 	type CookieConfig
-} from '../cookies';
+} from '../cookies.server';
+
+const mockEnv = vi.hoisted(() => ({}) as Record<string, string | undefined>);
 
 // Mock SvelteKit's dev environment
 vi.mock('$app/environment', () => ({
 	dev: false
 }));
 
+// Mock private environment
+vi.mock('$env/dynamic/private', () => ({
+	env: mockEnv
+}));
+
 describe('Cookie Utilities', () => {
 	let mockCookies: any;
 
@@ -118,13 +127,13 @@ describe('Cookie Utilities', () => {
 		it('should delete access_token cookie', () => {
 			clearAuthCookies(mockCookies);
 
-			expect(mockCookies.delete).toHaveBeenCalledWith('access_token', { path: '/' });
+			expect(mockCookies.delete).toHaveBeenCalledWith('access_token', { path: '/', secure: true });
 		});
 
 		it('should delete user cookie', () => {
 			clearAuthCookies(mockCookies);
 
-			expect(mockCookies.delete).toHaveBeenCalledWith('user', { path: '/' });
+			expect(mockCookies.delete).toHaveBeenCalledWith('user', { path: '/', secure: true });
 		});
 
 		it('should delete both cookies in correct order', () => {
@@ -344,4 +353,162 @@ describe('Cookie Utilities', () => {
 			);
 		});
 	});
+
+	describe('Configured cookie path', () => {
+		afterEach(() => {
+			delete mockEnv.SESSION_COOKIE_PATH;
+			vi.resetModules();
+		});
+
+		it('should set and clear cookies on the configured path', async () => {
+			mockEnv.SESSION_COOKIE_PATH = '/geni';
+			vi.resetModules();
+			const cookies = await import('../cookies.server');
+
+			cookies.setAuthCookie(mockCookies, 'jwt.services');
+			cookies.clearAuthCookies(mockCookies);
+
+			expect(mockCookies.set).toHaveBeenCalledWith(
+				'access_token',
+				'jwt.services',
+				expect.objectContaining({ path: '/geni' })
+			);
+			expect(mockCookies.delete).toHaveBeenCalledWith('access_token', {
+				path: '/geni',
+				secure: true
+			});
+			expect(mockCookies.delete).toHaveBeenCalledWith('user', {
+				path: '/geni',
+				secure: true
+			});
+		});
+	});
+
+	describe('Secure cookie policy', () => {
+		afterEach(() => {
+			delete mockEnv.SECURE_COOKIES;
+			vi.resetModules();
+		});
+
+		it('should warn when Secure cookies are served from an http origin', () => {
+			expect(getCookieSecurityWarning('http://geni.example.com')).toContain('SECURE_COOKIES');
+		});
+
+		it('should not warn for an https origin', () => {
+			expect(getCookieSecurityWarning('https://geni.example.com')).toBeUndefined();
+		});
+
+		it('should not warn when no origin is configured', () => {
+			expect(getCookieSecurityWarning(undefined)).toBeUndefined();
+		});
+
+		it('should drop the Secure attribute and the warning when disabled', async () => {
+			mockEnv.SECURE_COOKIES = 'false';
+			vi.resetModules();
+			const cookies = await import('../cookies.server');
+
+			cookies.setAuthCookie(mockCookies, 'jwt.services');
+
+			expect(mockCookies.set).toHaveBeenCalledWith(
+				'access_token',
+				'jwt.services',
+				expect.objectContaining({ secure: false })
+			);
+			expect(cookies.getCookieSecurityWarning('http://localhost:5173')).toBeUndefined();
+		});
+
+		it('should clear cookies without the Secure attribute when disabled', async () => {
+			mockEnv.SECURE_COOKIES = 'false';
+			vi.resetModules();
+			const cookies = await import('../cookies.server');
+
+			cookies.clearAuthCookies(mockCookies);
+
+			expect(mockCookies.delete).toHaveBeenCalledWith(
+				'access_token',
+				expect.objectContaining({ secure: false })
+			);
+			expect(mockCookies.delete).toHaveBeenCalledWith(
+				'user',
+				expect.objectContaining({ secure: false })
+			);
+		});
+
+		it('should accept the same false spellings as the other boolean settings', async () => {
+			mockEnv.SECURE_COOKIES = 'OFF';
+			vi.resetModules();
+			const cookies = await import('../cookies.server');
+
+			expect(cookies.COOKIE_OPTIONS.secure).toBe(false);
+		});
+	});
+
+	describe('Cookie prefixes', () => {
+		afterEach(() => {
+			delete mockEnv.SESSION_COOKIE_PREFIX;
+			vi.resetModules();
+		});
+
+		it('should accept __Host- with Secure, Path=/ and no Domain', () => {
+			expect(() =>
+				assertCookiePrefixConstraints('__Host-', { ...COOKIE_OPTIONS, secure: true, path: '/' })
+			).not.toThrow();
+		});
+
+		it('should reject __Host- with a Domain', () => {
+			expect(() =>
+				assertCookiePrefixConstraints('__Host-', {
+					...COOKIE_OPTIONS,
+					secure: true,
+					domain: 'example.com'
+				})
+			).toThrow('must not set a Domain');
+		});
+
+		it('should reject __Host- on a subpath', () => {
+			expect(() =>
+				assertCookiePrefixConstraints('__Host-', { ...COOKIE_OPTIONS, path: '/geni' })
+			).toThrow('require Path=/');
+		});
+
+		it('should reject prefixes without Secure', () => {
+			expect(() =>
+				assertCookiePrefixConstraints('__Secure-', { ...COOKIE_OPTIONS, secure: false })
+			).toThrow('require Secure');
+		});
+
+		it('should allow a Domain with __Secure-', () => {
+			expect(() =>
+				assertCookiePrefixConstraints('__Secure-', {
+					...COOKIE_OPTIONS,
+					secure: true,
+					domain: 'example.com'
+				})
+			).not.toThrow();
+		});
+
+		it('should use the prefixed cookie name when configured', async () => {
+			mockEnv.SESSION_COOKIE_PREFIX = '__Host-';
+			vi.resetModules();
+			const cookies = await import('../cookies.server');
+
+			cookies.setAuthCookie(mockCookies, 'jwt.services');
+			cookies.getAuthToken(mockCookies);
+
+			expect(mockCookies.set).toHaveBeenCalledWith(
+				'__Host-access_token',
+				'jwt.services',
+				cookies.COOKIE_OPTIONS
+			);
+			expect(mockCookies.get).toHaveBeenCalledWith('__Host-access_token');
+			expect(() => cookies.assertCookiePrefixConstraints()).not.toThrow();
+		});
+
+		it('should reject unknown prefixes', async () => {
+			mockEnv.SESSION_COOKIE_PREFIX = '__Bogus-';
+			vi.resetModules();
+
+			await expect(import('../cookies.server')).rejects.toThrow('SESSION_COOKIE_PREFIX');
+		});
+	});
 });
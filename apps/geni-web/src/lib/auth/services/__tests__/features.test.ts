@@ -0,0 +1,67 @@
+import { describe, it, expect, vi } from 'vitest';
+import { getFeatureFlags, isRouteDisabled } from '../features.server';
+
+vi.mock('$env/dynamic/private', () => ({
+	env: {}
+}));
+
+describe('Feature Flags', () => {
+	describe('getFeatureFlags', () => {
+		it('should enable every method by default', () => {
+			expect(getFeatureFlags({})).toEqual({
+				magicLink: true,
+				phoneOtp: true,
+				passkeys: true,
+				oauth: true
+			});
+		});
+
+		it('should disable methods set to false', () => {
+			const flags = getFeatureFlags({ ENABLE_OAUTH: 'false', ENABLE_PHONE_OTP: '0' });
+
+			expect(flags.oauth).toBe(false);
+			expect(flags.phoneOtp).toBe(false);
+			expect(flags.magicLink).toBe(true);
+		});
+
+		it('should ignore case and whitespace', () => {
+			expect(getFeatureFlags({ ENABLE_PASSKEYS: ' FALSE ' }).passkeys).toBe(false);
+		});
+
+		it('should accept off and no', () => {
+			const flags = getFeatureFlags({ ENABLE_OAUTH: 'off', ENABLE_PASSKEYS: 'No' });
+
+			expect(flags.oauth).toBe(false);
+			expect(flags.passkeys).toBe(false);
+		});
+	});
+
+	describe('isRouteDisabled', () => {
+		it('should hide routes of a disabled method', () => {
+			const flags = getFeatureFlags({ ENABLE_MAGIC_LINK: 'false' });
+
+			expect(isRouteDisabled('/api/sign-in/magic-link', flags)).toBe(true);
+			expect(isRouteDisabled('/api/sign-in/magic-link/verify', flags)).toBe(true);
+		});
+
+		it('should keep routes of enabled methods', () => {
+			const flags = getFeatureFlags({ ENABLE_MAGIC_LINK: 'false' });
+
+			expect(isRouteDisabled('/api/sign-in/phone-otp', flags)).toBe(false);
+		});
+
+		it('should only match whole path segments', () => {
+			const flags = getFeatureFlags({ ENABLE_OAUTH: 'false' });
+
+			expect(isRouteDisabled('/api/sign-in/oauth/google', flags)).toBe(true);
+			expect(isRouteDisabled('/api/sign-in/oauthx', flags)).toBe(false);
+		});
+
+		it('should not affect unrelated routes', () => {
+			const flags = getFeatureFlags({ ENABLE_OAUTH: 'false', ENABLE_MAGIC_LINK: 'false' });
+
+			expect(isRouteDisabled('/', flags)).toBe(false);
+			expect(isRouteDisabled('/api/auth/config', flags)).toBe(false);
+		});
+	});
+});
@@ -1,5 +1,6 @@
-import { describe, it, expect, vi, beforeEach } from 'vitest';
+import { describe, it, expect, vi, beforeEach, afterEach } from 'vitest';
 import jwt from 'jsonwebtoken';
+import { createHmac } from 'crypto';
 import {
 	generateToken,
 	verifyToken,
@@ -17,8 +18,16 @@ vi.mock('jsonwebtoken', () => ({
 	}
 }));
 
+// Mock private environment
+vi.mock('$env/dynamic/private', () => ({
+	env: {
+		JWT_SECRET: 'test-secret',
+		JWT_SECRET_PREVIOUS: 'previous-secret'
+	}
+}));
+
 // Mock cookies module
-vi.mock('../cookies', () => ({
+vi.mock('../cookies.server', () => ({
 	getAuthToken: vi.fn(),
 	setAuthCookie: vi.fn(),
 	clearAuthCookies: vi.fn(),
@@ -355,4 +364,74 @@ describe('JWT Utilities', () => {
 			expect(result.user?.role).toBeUndefined();
 		});
 	});
+
+	describe('Expiry leeway', () => {
+		const issuedAt = new Date('2025-01-01T00:00:00Z');
+		const expiresAt = issuedAt.getTime() + 60 * 60 * 1000;
+
+		beforeEach(() => {
+			vi.useFakeTimers();
+			vi.setSystemTime(issuedAt);
+		});
+
+		afterEach(() => {
+			vi.useRealTimers();
+		});
+
+		it('should accept a token just past expiry within the leeway', () => {
+			const token = generateToken({ userId: 'user123', email: 'user@example.com' });
+
+			vi.setSystemTime(expiresAt + 4 * 1000);
+
+			expect(verifyToken(token).success).toBe(true);
+		});
+
+		it('should reject a token expired beyond the leeway', () => {
+			const token = generateToken({ userId: 'user123', email: 'user@example.com' });
+
+			vi.setSystemTime(expiresAt + 6 * 1000);
+
+			const result = verifyToken(token);
+
+			expect(result.success).toBe(false);
+			expect(result.message).toBe('Token has expired');
+		});
+	});
+
+	describe('Secret rotation', () => {
+		// Build a token signed with an arbitrary secret
+		function signWith(secret: string): string {
+			const encode = (value: object) => Buffer.from(JSON.stringify(value)).toString('base64url');
+			const message = `${encode({ alg: 'HS256', typ: 'JWT' })}.${encode({
+				userId: 'user123',
+				email: 'user@example.com',
+				exp: Math.floor(Date.now() / 1000) + 3600
+			})}`;
+			return `${message}.${createHmac('sha256', secret).update(message).digest('base64url')}`;
+		}
+
+		it('should verify tokens signed with the current secret', () => {
+			expect(verifyToken(signWith('test-secret')).success).toBe(true);
+		});
+
+		it('should verify tokens signed with the previous secret', () => {
+			expect(verifyToken(signWith('previous-secret')).success).toBe(true);
+		});
+
+		it('should reject tokens signed with any other secret', () => {
+			const result = verifyToken(signWith('unknown-secret'));
+
+			expect(result.success).toBe(false);
+			expect(result.message).toBe('Invalid token signature');
+		});
+
+		it('should reject signatures of a different length without throwing', () => {
+			const token = signWith('test-secret');
+			const truncated = token.slice(0, -4);
+			const multibyte = `${token.slice(0, -1)}é`;
+
+			expect(verifyToken(truncated).message).toBe('Invalid token signature');
+			expect(verifyToken(multibyte).message).toBe('Invalid token signature');
+		});
+	});
 });
@@ -0,0 +1,48 @@
+import { describe, it, expect } from 'vitest';
+import { toSessionUser } from '../session-user';
+import type { Email } from '$lib/utils/email.server';
+
+describe('toSessionUser', () => {
+	const dbUser = {
+		id: 'user123',
+		name: 'John Doe',
+		email: 'john@example.com' as Email,
+		phone: null,
+		plan: 'Plus' as const,
+		onboardingCompleted: true,
+		lastSignIn: new Date('2025-01-02T00:00:00Z'),
+		lastSignInMethod: 'magic-link',
+		createdAt: new Date('2025-01-01T00:00:00Z'),
+		updatedAt: new Date('2025-01-02T00:00:00Z')
+	};
+
+	it('should map the exposed fields', () => {
+		expect(toSessionUser(dbUser)).toEqual({
+			id: 'user123',
+			email: 'john@example.com',
+			phone: undefined,
+			name: 'John Doe',
+			plan: 'Plus',
+			created_at: '2025-01-01T00:00:00.000Z',
+			onboarding_completed: true
+		});
+	});
+
+	it('should omit internal fields', () => {
+		const sessionUser = toSessionUser(dbUser);
+
+		expect(sessionUser).not.toHaveProperty('lastSignIn');
+		expect(sessionUser).not.toHaveProperty('lastSignInMethod');
+		expect(sessionUser).not.toHaveProperty('updatedAt');
+	});
+
+	it('should not pass through columns it does not know about', () => {
+		const sessionUser = toSessionUser({ ...dbUser, passwordHash: 'secret' } as any);
+
+		expect(sessionUser).not.toHaveProperty('passwordHash');
+	});
+
+	it('should default a missing name to an empty string', () => {
+		expect(toSessionUser({ ...dbUser, name: null }).name).toBe('');
+	});
+});
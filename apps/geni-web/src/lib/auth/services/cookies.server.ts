@@ -0,0 +1,136 @@
+import type { Cookies } from '@sveltejs/kit';
+import { dev } from '$app/environment';
+import { env } from '$env/dynamic/private';
+import { parseBooleanEnv } from '$lib/utils/env';
+
+export interface CookieConfig {
+	path: string;
+	httpOnly: boolean;
+	secure: boolean;
+	sameSite: 'strict' | 'lax' | 'none';
+	maxAge: number;
+	domain?: string;
+}
+
+// Browser-enforced cookie name prefixes, see https://developer.mozilla.org/docs/Web/HTTP/Cookies#cookie_prefixes
+export type CookiePrefix = '' | '__Secure-' | '__Host-';
+
+const COOKIE_PREFIX = parseCookiePrefix(env.SESSION_COOKIE_PREFIX);
+const ACCESS_TOKEN_COOKIE_NAME = `${COOKIE_PREFIX}access_token`;
+const COOKIE_PATH = env.SESSION_COOKIE_PATH || '/'; // Scope cookies to a subpath behind path-based gateways
+const COOKIE_DOMAIN = env.SESSION_COOKIE_DOMAIN || undefined; // Share cookies across subdomains
+// Secure in production, allow plain HTTP in development; SECURE_COOKIES overrides either way
+const SECURE_COOKIES = parseBooleanEnv(env.SECURE_COOKIES, !dev);
+
+// Configuration for authentication cookies
+export const COOKIE_OPTIONS: CookieConfig = {
+	path: COOKIE_PATH,
+	httpOnly: true,
+	secure: SECURE_COOKIES,
+	sameSite: 'strict',
+	maxAge: 60 * 60, // 1 hour in seconds
+	...(COOKIE_DOMAIN && { domain: COOKIE_DOMAIN })
+};
+
+// Path, domain and Secure must match when deleting, or the browser keeps the cookie;
+// SvelteKit's delete otherwise defaults to Secure on any host but http://localhost
+const COOKIE_SCOPE = {
+	path: COOKIE_PATH,
+	secure: SECURE_COOKIES,
+	...(COOKIE_DOMAIN && { domain: COOKIE_DOMAIN })
+};
+
+/**
+ * Parse the configured cookie prefix
+ * @throws Error if the prefix is not one the browser recognizes
+ */
+function parseCookiePrefix(value: string | undefined): CookiePrefix {
+	if (!value) {
+		return '';
+	}
+	if (value !== '__Secure-' && value !== '__Host-') {
+		throw new Error(`SESSION_COOKIE_PREFIX must be __Secure- or __Host-, got ${value}`);
+	}
+	return value;
+}
+
+/**
+ * Check that a cookie config satisfies its name prefix, which browsers otherwise silently reject
+ * @param prefix - Cookie name prefix
+ * @param config - Cookie attributes
+ * @throws Error describing the first violated constraint
+ */
+export function assertCookiePrefixConstraints(
+	prefix: CookiePrefix = COOKIE_PREFIX,
+	config: CookieConfig = COOKIE_OPTIONS
+): void {
+	if (!prefix) {
+		return;
+	}
+	if (!config.secure) {
+		throw new Error(`${prefix} cookies require Secure; remove SECURE_COOKIES=false`);
+	}
+	if (prefix === '__Host-' && config.path !== '/') {
+		throw new Error('__Host- cookies require Path=/; unset SESSION_COOKIE_PATH');
+	}
+	if (prefix === '__Host-' && config.domain) {
+		throw new Error('__Host- cookies must not set a Domain; unset SESSION_COOKIE_DOMAIN');
+	}
+}
+
+/**
+ * Set authentication services in cookies
+ * @param cookies - SvelteKit Cookies object
+ * @param accessToken - JWT access services to store
+ * @throws Error if services is empty or whitespace-only
+ */
+export function setAuthCookie(cookies: Cookies, accessToken: string): void {
+	if (!accessToken || !accessToken.trim()) {
+		throw new Error('Access services cannot be empty');
+	}
+
+	cookies.set(ACCESS_TOKEN_COOKIE_NAME, accessToken, COOKIE_OPTIONS);
+}
+
+/**
+ * Clear all authentication cookies
+ * @param cookies - SvelteKit Cookies object
+ */
+export function clearAuthCookies(cookies: Cookies): void {
+	cookies.delete(ACCESS_TOKEN_COOKIE_NAME, COOKIE_SCOPE);
+	// Clear any other auth-related cookies
+	cookies.delete('user', COOKIE_SCOPE);
+}
+
+/**
+ * Get authentication services from cookies
+ * @param cookies - SvelteKit Cookies object
+ * @returns Access services string, or undefined if not found
+ */
+export function getAuthToken(cookies: Cookies): string | undefined {
+	return cookies.get(ACCESS_TOKEN_COOKIE_NAME);
+}
+
+/**
+ * Check if user is authenticated by checking for valid cookie
+ * @param cookies - SvelteKit Cookies object
+ * @returns True if access services cookie exists
+ */
+export function isAuthenticated(cookies: Cookies): boolean {
+	return !!getAuthToken(cookies);
+}
+
+/**
+ * Check for a Secure cookie policy that browsers will silently ignore
+ * @param origin - Public origin the app is served from (e.g. the ORIGIN env var)
+ * @returns Warning message if Secure cookies are enabled on an http:// origin
+ */
+export function getCookieSecurityWarning(origin: string | undefined): string | undefined {
+	if (COOKIE_OPTIONS.secure && origin?.startsWith('http://')) {
+		return (
+			`Secure cookies are enabled but ORIGIN is ${origin}; browsers will not store auth ` +
+			'cookies over plain HTTP. Serve over HTTPS or set SECURE_COOKIES=false for local testing.'
+		);
+	}
+	return undefined;
+}
@@ -0,0 +1,50 @@
+import { env } from '$env/dynamic/private';
+import { parseBooleanEnv } from '$lib/utils/env';
+
+// Types
+export interface FeatureFlags {
+	magicLink: boolean;
+	phoneOtp: boolean;
+	passkeys: boolean;
+	oauth: boolean;
+}
+
+type EnvSource = Record<string, string | undefined>;
+
+// Route prefixes owned by each sign-in method
+const FEATURE_ROUTES: Record<keyof FeatureFlags, string[]> = {
+	magicLink: ['/api/sign-in/magic-link'],
+	phoneOtp: ['/api/sign-in/phone-otp'],
+	passkeys: ['/api/sign-in/passkey'],
+	oauth: ['/api/sign-in/oauth']
+};
+
+/**
+ * Build feature flags from environment variables; unset methods are enabled
+ * @param source - Environment to read from (defaults to private env)
+ * @returns Enabled state of each sign-in method
+ */
+export function getFeatureFlags(source: EnvSource = env): FeatureFlags {
+	return {
+		magicLink: parseBooleanEnv(source.ENABLE_MAGIC_LINK, true),
+		phoneOtp: parseBooleanEnv(source.ENABLE_PHONE_OTP, true),
+		passkeys: parseBooleanEnv(source.ENABLE_PASSKEYS, true),
+		oauth: parseBooleanEnv(source.ENABLE_OAUTH, true)
+	};
+}
+
+/**
+ * Check if a route belongs to a disabled sign-in method
+ * @param pathname - Request path
+ * @param flags - Current feature flags
+ * @returns True if the route should be hidden
+ */
+export function isRouteDisabled(pathname: string, flags: FeatureFlags): boolean {
+	return (Object.keys(FEATURE_ROUTES) as (keyof FeatureFlags)[]).some(
+		(feature) =>
+			!flags[feature] &&
+			FEATURE_ROUTES[feature].some(
+				(route) => pathname === route || pathname.startsWith(`${route}/`)
+			)
+	);
+}
@@ -1,2 +1,4 @@
 export * from './jwt.server'
-export * from './cookies'
+export * from './cookies.server'
+export * from './features.server'
+export * from './session-user'
@@ -1,11 +1,12 @@
-import { createHmac } from 'crypto';
+import { createHmac, timingSafeEqual } from 'crypto';
 import type { Cookies } from '@sveltejs/kit';
-import { getAuthToken } from './cookies';
+import { getAuthToken } from './cookies.server';
 import { env } from '$env/dynamic/private';
+import { EXPIRY_LEEWAY_SECONDS } from '$lib/utils/expiry.server';
 
 
 // Re-export cookie functions for backward compatibility
-export { setAuthCookie, clearAuthCookies, getAuthToken } from './cookies';
+export { setAuthCookie, clearAuthCookies, getAuthToken } from './cookies.server';
 
 // Types
 export interface JwtPayload {
@@ -30,6 +31,8 @@ export interface DecodedToken {
 
 // Configuration
 const JWT_SECRET = env.JWT_SECRET;
+// Still accepted for verification while rotating
+const JWT_SECRET_PREVIOUS = env.JWT_SECRET_PREVIOUS;
 const ACCESS_TOKEN_EXPIRY_MS = 60 * 60 * 1000; // 1 hour in milliseconds
 
 /**
@@ -56,6 +59,28 @@ function base64UrlDecode(str: string): string {
 	return Buffer.from(base64, 'base64').toString('utf-8');
 }
 
+/**
+ * Sign a message with HMAC-SHA256, returning a base64url signature
+ */
+function sign(message: string, secret: string): string {
+	return createHmac('sha256', secret)
+		.update(message)
+		.digest('base64')
+		.replace(/\+/g, '-')
+		.replace(/\//g, '_')
+		.replace(/=/g, '');
+}
+
+/**
+ * Check a signature against the one expected for a secret, in constant time
+ */
+function signatureMatches(signature: string, message: string, secret: string): boolean {
+	const expected = Buffer.from(sign(message, secret));
+	const actual = Buffer.from(signature);
+	// timingSafeEqual throws on mismatched lengths; a length mismatch alone reveals nothing secret
+	return actual.length === expected.length && timingSafeEqual(actual, expected);
+}
+
 /**
  * Generate a JWT token for a user
  * @param payload - User data to encode in token
@@ -88,13 +113,8 @@ export function generateToken(payload: Omit<JwtPayload, 'iat' | 'exp'>): string
 	const encodedPayload = base64UrlEncode(JSON.stringify(fullPayload));
 	const message = `${encodedHeader}.${encodedPayload}`;
 
-	// Create signature using HMAC-SHA256
-	const signature = createHmac('sha256', JWT_SECRET)
-		.update(message)
-		.digest('base64')
-		.replace(/\+/g, '-')
-		.replace(/\//g, '_')
-		.replace(/=/g, '');
+	// Create signature using HMAC-SHA256, always with the current secret
+	const signature = sign(message, JWT_SECRET);
 
 	return `${message}.${signature}`;
 }
@@ -123,16 +143,14 @@ export function verifyToken(token: string): TokenResponse {
 
 		const [encodedHeader, encodedPayload, signature] = parts;
 
-		// Verify signature
+		// Verify signature against the current secret, or the previous one during rotation
 		const message = `${encodedHeader}.${encodedPayload}`;
-		const expectedSignature = createHmac('sha256', JWT_SECRET)
-			.update(message)
-			.digest('base64')
-			.replace(/\+/g, '-')
-			.replace(/\//g, '_')
-			.replace(/=/g, '');
-
-		if (signature !== expectedSignature) {
+		const secrets = JWT_SECRET_PREVIOUS ? [JWT_SECRET, JWT_SECRET_PREVIOUS] : [JWT_SECRET];
+
+		// Compare against every secret so timing doesn't reveal which one matched
+		const matches = secrets.map((secret) => signatureMatches(signature, message, secret));
+
+		if (!matches.includes(true)) {
 			return {
 				success: false,
 				message: 'Invalid token signature'
@@ -143,9 +161,9 @@ export function verifyToken(token: string): TokenResponse {
 		const payloadString = base64UrlDecode(encodedPayload);
 		const decoded = JSON.parse(payloadString) as JwtPayload;
 
-		// Check expiration
+		// Check expiration, tolerating small clock skew
 		const now = Math.floor(Date.now() / 1000);
-		if (decoded.exp && decoded.exp < now) {
+		if (decoded.exp && decoded.exp + EXPIRY_LEEWAY_SECONDS < now) {
 			return {
 				success: false,
 				message: 'Token has expired'
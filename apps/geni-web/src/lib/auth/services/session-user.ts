@@ -0,0 +1,34 @@
+import type { users } from '$lib/db/schemas/users';
+import type { UserPlan } from '$lib/billing/plans';
+import type { Email } from '$lib/utils/email.server';
+
+type UserRow = typeof users.$inferSelect;
+
+// Fields of the signed-in user exposed to routes and pages
+export interface SessionUser {
+	id: string;
+	email?: Email;
+	phone?: string;
+	name: string;
+	plan: UserPlan;
+	created_at: string;
+	onboarding_completed: boolean;
+}
+
+/**
+ * Map a database user to the fields safe to expose to routes and pages
+ * Fields are picked explicitly so new columns are never exposed by accident
+ * @param user - User row from the database
+ * @returns Sanitized session user
+ */
+export function toSessionUser(user: UserRow): SessionUser {
+	return {
+		id: user.id,
+		email: user.email ?? undefined,
+		phone: user.phone ?? undefined,
+		name: user.name ?? '',
+		plan: user.plan,
+		created_at: user.createdAt.toISOString(),
+		onboarding_completed: user.onboardingCompleted
+	};
+}
@@ -0,0 +1,57 @@
+import { describe, it, expect } from 'vitest';
+import { isUserPlan, planRank, comparePlans, isAtLeast, isDowngrade } from '../plans';
+
+describe('Plan hierarchy', () => {
+	describe('isUserPlan', () => {
+		it('should accept known plans', () => {
+			expect(isUserPlan('Premium')).toBe(true);
+		});
+
+		it('should reject unknown values', () => {
+			expect(isUserPlan('Enterprise')).toBe(false);
+			expect(isUserPlan(undefined)).toBe(false);
+		});
+	});
+
+	describe('planRank', () => {
+		it('should order FreeTrial < Plus < Premium < Lifetime', () => {
+			expect(planRank('FreeTrial')).toBeLessThan(planRank('Plus'));
+			expect(planRank('Plus')).toBeLessThan(planRank('Premium'));
+			expect(planRank('Premium')).toBeLessThan(planRank('Lifetime'));
+		});
+	});
+
+	describe('comparePlans', () => {
+		it('should return zero for the same plan', () => {
+			expect(comparePlans('Plus', 'Plus')).toBe(0);
+		});
+
+		it('should sort plans in ascending order', () => {
+			const plans = ['Lifetime', 'FreeTrial', 'Premium', 'Plus'] as const;
+
+			expect([...plans].sort(comparePlans)).toEqual(['FreeTrial', 'Plus', 'Premium', 'Lifetime']);
+		});
+	});
+
+	describe('isAtLeast', () => {
+		it('should be true for equal or higher plans', () => {
+			expect(isAtLeast('Plus', 'Plus')).toBe(true);
+			expect(isAtLeast('Lifetime', 'Plus')).toBe(true);
+		});
+
+		it('should be false for lower plans', () => {
+			expect(isAtLeast('FreeTrial', 'Plus')).toBe(false);
+		});
+	});
+
+	describe('isDowngrade', () => {
+		it('should detect downgrades', () => {
+			expect(isDowngrade('Premium', 'Plus')).toBe(true);
+		});
+
+		it('should not treat upgrades or no-ops as downgrades', () => {
+			expect(isDowngrade('Plus', 'Premium')).toBe(false);
+			expect(isDowngrade('Plus', 'Plus')).toBe(false);
+		});
+	});
+});
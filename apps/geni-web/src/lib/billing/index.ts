@@ -0,0 +1,8 @@
+/**
+ * Billing Module
+ * Plan ordering and comparison
+ */
+
+export type { UserPlan } from './plans';
+
+export { isUserPlan, planRank, comparePlans, isAtLeast, isDowngrade } from './plans';
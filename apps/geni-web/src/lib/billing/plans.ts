@@ -0,0 +1,46 @@
+import { planEnum } from '$lib/db/schemas/users';
+
+export type UserPlan = (typeof planEnum.enumValues)[number];
+
+// Plans in ascending order of entitlement
+const PLAN_ORDER: readonly UserPlan[] = planEnum.enumValues;
+
+/**
+ * Check if a value is a known plan
+ */
+export function isUserPlan(value: unknown): value is UserPlan {
+	return PLAN_ORDER.includes(value as UserPlan);
+}
+
+/**
+ * Get the rank of a plan (higher is more entitled)
+ * @param plan - Plan to rank
+ * @returns Zero-based rank, FreeTrial being 0
+ */
+export function planRank(plan: UserPlan): number {
+	return PLAN_ORDER.indexOf(plan);
+}
+
+/**
+ * Compare two plans
+ * @returns Negative if a < b, zero if equal, positive if a > b
+ */
+export function comparePlans(a: UserPlan, b: UserPlan): number {
+	return planRank(a) - planRank(b);
+}
+
+/**
+ * Check if a plan is at least as entitled as another
+ * @param plan - Plan to check
+ * @param minimum - Required plan
+ */
+export function isAtLeast(plan: UserPlan, minimum: UserPlan): boolean {
+	return comparePlans(plan, minimum) >= 0;
+}
+
+/**
+ * Check if moving between two plans is a downgrade
+ */
+export function isDowngrade(from: UserPlan, to: UserPlan): boolean {
+	return comparePlans(to, from) < 0;
+}
@@ -1,17 +1,50 @@
-import { describe, it, expect, beforeEach, vi } from 'vitest';
+import { describe, it, expect, beforeEach, afterEach, vi } from 'vitest';
 import {
 	userExistsByEmail,
 	userExistsByPhone,
+	findUsersByIds,
 	createUser,
+	upsertUserByEmail,
+	upsertIdentity,
 	deleteUser,
+	previewUserDeletion,
 	logJWTToken,
 	expireOldJWTLogs,
 	logMagicLink,
-	expireOldMagicLinks
+	isMagicLinkValid,
+	consumeMagicLink,
+	deleteMagicLinksForIdentifier,
+	expireOldMagicLinks,
+	EmailInUseError,
+	IdentityInUseError
 } from '../users';
 import { db } from '$lib/db/client';
+import { parseEmail } from '$lib/utils/email.server';
+import * as schema from '$lib/db/schemas/users';
+import { is } from 'drizzle-orm';
+import { PgDialect, PgTable, getTableConfig } from 'drizzle-orm/pg-core';
+import type { SQL } from 'drizzle-orm';
+
+const mockEnv = vi.hoisted(() => ({}) as Record<string, string | undefined>);
 
 vi.mock('$lib/db/client');
+vi.mock('$env/dynamic/private', () => ({
+	env: mockEnv
+}));
+
+// Renders a captured `where` predicate so tests can assert on the filter itself
+function renderWhere(predicate: SQL) {
+	return new PgDialect().sqlToQuery(predicate);
+}
+
+// Build an Email for test input; operations only accept parsed addresses
+function toEmail(value: string) {
+	const email = parseEmail(value);
+	if (!email) {
+		throw new Error(`Invalid test email: ${value}`);
+	}
+	return email;
+}
 
 describe('User Operations', () => {
 	beforeEach(() => {
@@ -23,7 +56,7 @@ describe('User Operations', () => {
 			const mockUser = { id: '123', email: 'test@example.com' };
 			vi.mocked(db.query.users.findFirst).mockResolvedValue(mockUser as any);
 
-			const result = await userExistsByEmail('test@example.com');
+			const result = await userExistsByEmail(toEmail('test@example.com'));
 
 			expect(result).toBe(true);
 			expect(db.query.users.findFirst).toHaveBeenCalledWith({
@@ -34,7 +67,7 @@ describe('User Operations', () => {
 		it('should return false if user with email does not exist', async () => {
 			vi.mocked(db.query.users.findFirst).mockResolvedValue(undefined);
 
-			const result = await userExistsByEmail('nonexistent@example.com');
+			const result = await userExistsByEmail(toEmail('nonexistent@example.com'));
 
 			expect(result).toBe(false);
 		});
@@ -62,11 +95,33 @@ describe('User Operations', () => {
 		});
 	});
 
+	describe('findUsersByIds', () => {
+		it('should return only the users that exist', async () => {
+			const existingUsers = [
+				{ id: '123', email: 'a@example.com' },
+				{ id: '456', email: 'b@example.com' }
+			];
+			vi.mocked(db.query.users.findMany).mockResolvedValue(existingUsers as any);
+
+			const result = await findUsersByIds(['123', '456', 'missing']);
+
+			expect(result).toEqual(existingUsers);
+			expect(db.query.users.findMany).toHaveBeenCalledTimes(1);
+		});
+
+		it('should not query for an empty list', async () => {
+			const result = await findUsersByIds([]);
+
+			expect(result).toEqual([]);
+			expect(db.query.users.findMany).not.toHaveBeenCalled();
+		});
+	});
+
 	describe('createUser', () => {
 		it('should create a user with valid data', async () => {
 			const userData = {
 				name: 'John Doe',
-				email: 'john@example.com',
+				email: toEmail('john@example.com'),
 				phone: '+1234567890'
 			};
 
@@ -96,7 +151,7 @@ describe('User Operations', () => {
 		it('should throw error if email already exists', async () => {
 			const userData = {
 				name: 'John Doe',
-				email: 'existing@example.com'
+				email: toEmail('existing@example.com')
 			};
 
 			vi.mocked(db.query.users.findFirst).mockResolvedValue({
@@ -109,6 +164,59 @@ describe('User Operations', () => {
 			);
 		});
 
+		it('should throw EmailInUseError with a 409 status for a taken email', async () => {
+			vi.mocked(db.query.users.findFirst).mockResolvedValue({
+				id: '456',
+				email: 'existing@example.com'
+			} as any);
+
+			const error = await createUser({ email: toEmail('existing@example.com') }).catch((e) => e);
+
+			expect(error).toBeInstanceOf(EmailInUseError);
+			expect(error.status).toBe(409);
+			expect(error.code).toBe('email_in_use');
+		});
+
+		it('should throw EmailInUseError when a concurrent signup wins the insert', async () => {
+			vi.mocked(db.query.users.findFirst).mockResolvedValue(undefined);
+			vi.mocked(db.insert).mockReturnValue({
+				values: vi.fn().mockReturnValue({
+					returning: vi.fn().mockRejectedValue({ code: '23505' })
+				})
+			} as any);
+
+			const error = await createUser({ email: toEmail('race@example.com') }).catch((e) => e);
+
+			expect(error).toBeInstanceOf(EmailInUseError);
+			expect(error.status).toBe(409);
+		});
+
+		it('should unwrap Drizzle query errors for the email constraint', async () => {
+			const cause = { code: '23505', constraint: 'users_email_unique' };
+			vi.mocked(db.query.users.findFirst).mockResolvedValue(undefined);
+			vi.mocked(db.insert).mockReturnValue({
+				values: vi.fn().mockReturnValue({
+					returning: vi.fn().mockRejectedValue(new Error('Failed query', { cause }))
+				})
+			} as any);
+
+			const error = await createUser({ email: toEmail('race@example.com') }).catch((e) => e);
+
+			expect(error).toBeInstanceOf(EmailInUseError);
+		});
+
+		it('should rethrow other insert failures unchanged', async () => {
+			const failure = { code: '23505', constraint: 'users_pkey' };
+			vi.mocked(db.query.users.findFirst).mockResolvedValue(undefined);
+			vi.mocked(db.insert).mockReturnValue({
+				values: vi.fn().mockReturnValue({
+					returning: vi.fn().mockRejectedValue(failure)
+				})
+			} as any);
+
+			await expect(createUser({ email: toEmail('race@example.com') })).rejects.toBe(failure);
+		});
+
 		it('should throw error if phone already exists', async () => {
 			const userData = {
 				name: 'John Doe',
@@ -125,6 +233,105 @@ describe('User Operations', () => {
 			);
 		});
 
+		it('should derive a display name from the email when none is given', async () => {
+			const values = vi.fn().mockReturnValue({
+				returning: vi.fn().mockResolvedValue([{ id: '123' }])
+			});
+
+			vi.mocked(db.query.users.findFirst).mockResolvedValue(undefined);
+			vi.mocked(db.insert).mockReturnValue({ values } as any);
+
+			await createUser({ email: toEmail('john.doe+test@example.com') });
+
+			expect(values).toHaveBeenCalledWith(
+				expect.objectContaining({ email: 'john.doe+test@example.com', name: 'John Doe' })
+			);
+		});
+
+		it('should store the normalized email', async () => {
+			const values = vi.fn().mockReturnValue({
+				returning: vi.fn().mockResolvedValue([{ id: '123' }])
+			});
+
+			vi.mocked(db.query.users.findFirst).mockResolvedValue(undefined);
+			vi.mocked(db.insert).mockReturnValue({ values } as any);
+
+			await createUser({ name: 'John Doe', email: toEmail('  John@Example.com ') });
+
+			expect(values).toHaveBeenCalledWith(
+				expect.objectContaining({ email: 'john@example.com' })
+			);
+		});
+
+		it('should assign FreeTrial to new users by default', async () => {
+			const values = vi.fn().mockReturnValue({
+				returning: vi.fn().mockResolvedValue([{ id: '123' }])
+			});
+
+			vi.mocked(db.query.users.findFirst).mockResolvedValue(undefined);
+			vi.mocked(db.insert).mockReturnValue({ values } as any);
+
+			await createUser({ email: toEmail('new@example.com') });
+
+			expect(values).toHaveBeenCalledWith(expect.objectContaining({ plan: 'FreeTrial' }));
+		});
+
+		describe('configured default plan', () => {
+			afterEach(() => {
+				delete mockEnv.DEFAULT_NEW_USER_PLAN;
+				vi.resetModules();
+				vi.restoreAllMocks();
+			});
+
+			// Reload users.ts so the plan is read from the current env
+			async function loadFreshUsers() {
+				vi.resetModules();
+				const { createUser: freshCreateUser } = await import('../users');
+				const { db: freshDb } = await import('$lib/db/client');
+				const values = vi.fn().mockReturnValue({
+					returning: vi.fn().mockResolvedValue([{ id: '123' }])
+				});
+
+				vi.mocked(freshDb.query.users.findFirst).mockResolvedValue(undefined);
+				vi.mocked(freshDb.insert).mockReturnValue({ values } as any);
+
+				return { createUser: freshCreateUser, values };
+			}
+
+			it('should assign the configured default plan to new users', async () => {
+				mockEnv.DEFAULT_NEW_USER_PLAN = 'Plus';
+				const { createUser: createUserWithDefault, values } = await loadFreshUsers();
+
+				await createUserWithDefault({ email: toEmail('beta@example.com') });
+
+				expect(values).toHaveBeenCalledWith(expect.objectContaining({ plan: 'Plus' }));
+			});
+
+			it('should warn and fall back to FreeTrial for an unknown plan', async () => {
+				const warn = vi.spyOn(console, 'warn').mockImplementation(() => {});
+				mockEnv.DEFAULT_NEW_USER_PLAN = 'Gold';
+				const { createUser: createUserWithDefault, values } = await loadFreshUsers();
+
+				await createUserWithDefault({ email: toEmail('beta@example.com') });
+
+				expect(warn).toHaveBeenCalledWith(expect.stringContaining('"Gold"'));
+				expect(values).toHaveBeenCalledWith(expect.objectContaining({ plan: 'FreeTrial' }));
+			});
+		});
+
+		it('should keep an explicitly provided name', async () => {
+			const values = vi.fn().mockReturnValue({
+				returning: vi.fn().mockResolvedValue([{ id: '123' }])
+			});
+
+			vi.mocked(db.query.users.findFirst).mockResolvedValue(undefined);
+			vi.mocked(db.insert).mockReturnValue({ values } as any);
+
+			await createUser({ name: 'Johnny', email: toEmail('john.doe@example.com') });
+
+			expect(values).toHaveBeenCalledWith(expect.objectContaining({ name: 'Johnny' }));
+		});
+
 		it('should create user without email and phone', async () => {
 			const userData = {
 				name: 'John Doe'
@@ -155,6 +362,126 @@ describe('User Operations', () => {
 		});
 	});
 
+	describe('upsertUserByEmail', () => {
+		it('should insert the normalized email and only touch updatedAt on conflict', async () => {
+			const canonicalUser = { id: '123', email: 'new@example.com', plan: 'FreeTrial' };
+			const onConflictDoUpdate = vi.fn().mockReturnValue({
+				returning: vi.fn().mockResolvedValue([canonicalUser])
+			});
+			const values = vi.fn().mockReturnValue({ onConflictDoUpdate });
+
+			vi.mocked(db.insert).mockReturnValue({ values } as any);
+
+			const result = await upsertUserByEmail({ email: toEmail(' New@Example.com') });
+
+			expect(result).toEqual(canonicalUser);
+			expect(values).toHaveBeenCalledWith(
+				expect.objectContaining({ email: 'new@example.com' })
+			);
+			const [conflict] = onConflictDoUpdate.mock.calls[0];
+			expect(conflict.target).toBe(schema.users.email);
+			expect(Object.keys(conflict.set)).toEqual(['updatedAt']);
+			expect(db.query.users.findFirst).not.toHaveBeenCalled();
+		});
+	});
+
+	describe('upsertIdentity', () => {
+		it('should return the identity row on provider conflict', async () => {
+			const identity = {
+				id: 'identity1',
+				userId: '123',
+				provider: 'google',
+				providerId: 'google-sub',
+				email: 'new@example.com'
+			};
+			const onConflictDoUpdate = vi.fn().mockReturnValue({
+				returning: vi.fn().mockResolvedValue([identity])
+			});
+
+			vi.mocked(db.insert).mockReturnValue({
+				values: vi.fn().mockReturnValue({ onConflictDoUpdate })
+			} as any);
+
+			const result = await upsertIdentity({
+				userId: '123',
+				provider: 'google',
+				providerId: 'google-sub',
+				email: toEmail('new@example.com')
+			});
+
+			expect(result).toEqual(identity);
+			expect(onConflictDoUpdate).toHaveBeenCalledWith(
+				expect.objectContaining({ set: { email: 'new@example.com' } })
+			);
+		});
+
+		it('should only update a conflicting identity owned by the same user', async () => {
+			const onConflictDoUpdate = vi.fn().mockReturnValue({
+				returning: vi.fn().mockResolvedValue([{ id: 'identity1', userId: '123' }])
+			});
+
+			vi.mocked(db.insert).mockReturnValue({
+				values: vi.fn().mockReturnValue({ onConflictDoUpdate })
+			} as any);
+
+			await upsertIdentity({
+				userId: '123',
+				provider: 'google',
+				providerId: 'google-sub',
+				email: toEmail('new@example.com')
+			});
+
+			expect(onConflictDoUpdate).toHaveBeenCalledWith(
+				expect.objectContaining({ setWhere: expect.anything() })
+			);
+		});
+
+		it('should reject an identity already linked to another user', async () => {
+			// setWhere doesn't match, so Postgres skips the update and returns no row
+			vi.mocked(db.insert).mockReturnValue({
+				values: vi.fn().mockReturnValue({
+					onConflictDoUpdate: vi.fn().mockReturnValue({
+						returning: vi.fn().mockResolvedValue([])
+					})
+				})
+			} as any);
+
+			const error = await upsertIdentity({
+				userId: '456',
+				provider: 'google',
+				providerId: 'google-sub',
+				email: toEmail('other@example.com')
+			}).catch((e) => e);
+
+			expect(error).toBeInstanceOf(IdentityInUseError);
+			expect(error.status).toBe(409);
+		});
+	});
+
+	describe('Email typing', () => {
+		it('should only accept emails built by parseEmail', () => {
+			// Checked by the type checker; the calls are never run
+			const rawCalls = () => [
+				// @ts-expect-error a raw string is not an Email
+				userExistsByEmail('John@Example.com'),
+				// @ts-expect-error a raw string is not an Email
+				createUser({ email: 'John@Example.com' }),
+				// @ts-expect-error a raw string is not an Email
+				upsertUserByEmail({ email: 'John@Example.com' }),
+				upsertIdentity({
+					userId: '123',
+					provider: 'google',
+					providerId: 'google-sub',
+					// @ts-expect-error a raw string is not an Email
+					email: 'John@Example.com'
+				})
+			];
+
+			expect(rawCalls).toBeTypeOf('function');
+			expect(db.insert).not.toHaveBeenCalled();
+		});
+	});
+
 	describe('deleteUser', () => {
 		it('should delete user and return deleted user', async () => {
 			const deletedUser = { id: '123', name: 'John Doe' };
@@ -172,6 +499,42 @@ describe('User Operations', () => {
 		});
 	});
 
+	describe('previewUserDeletion', () => {
+		it('should return counts of identities and passkeys owned by the user', async () => {
+			vi.mocked(db.$count).mockResolvedValueOnce(2).mockResolvedValueOnce(1);
+
+			const result = await previewUserDeletion('123');
+
+			expect(result).toEqual({ identities: 2, passkeys: 1 });
+			expect(db.$count).toHaveBeenCalledTimes(2);
+		});
+
+		it('should not delete anything', async () => {
+			vi.mocked(db.$count).mockResolvedValue(0);
+
+			await previewUserDeletion('123');
+
+			expect(db.delete).not.toHaveBeenCalled();
+		});
+
+		it('should count exactly the tables that cascade when a user is deleted', async () => {
+			// deleteUser relies on ON DELETE CASCADE, so the schema defines what it removes
+			const cascadingTables = Object.values(schema)
+				.filter((table): table is PgTable => is(table, PgTable))
+				.filter((table) =>
+					getTableConfig(table).foreignKeys.some(
+						(fk) => fk.onDelete === 'cascade' && fk.reference().foreignTable === schema.users
+					)
+				);
+			vi.mocked(db.$count).mockResolvedValue(0);
+
+			await previewUserDeletion('123');
+
+			const countedTables = vi.mocked(db.$count).mock.calls.map(([table]) => table);
+			expect(new Set(countedTables)).toEqual(new Set(cascadingTables));
+		});
+	});
+
 	describe('logJWTToken', () => {
 		it('should log a JWT token', async () => {
 			const now = new Date();
@@ -279,10 +642,265 @@ describe('User Operations', () => {
 					returning: vi.fn().mockResolvedValue([logEntry])
 				})
 			} as any);
+			vi.mocked(db.query.magicLinks.findMany).mockResolvedValue([]);
 
 			const result = await logMagicLink(linkData);
 
 			expect(result).toEqual(logEntry);
+			expect(db.delete).not.toHaveBeenCalled();
+		});
+
+		it('should drop pending links beyond the newest five', async () => {
+			const where = vi.fn().mockResolvedValue(undefined);
+			vi.mocked(db.insert).mockReturnValue({
+				values: vi.fn().mockReturnValue({ returning: vi.fn().mockResolvedValue([]) })
+			} as any);
+			vi.mocked(db.query.magicLinks.findMany).mockResolvedValue([
+				{ tokenHash: 'old1' },
+				{ tokenHash: 'old2' }
+			] as any);
+			vi.mocked(db.delete).mockReturnValue({ where } as any);
+
+			await logMagicLink({
+				identifier: 'user@example.com',
+				tokenHash: 'hash123456',
+				expiresAt: new Date()
+			});
+
+			expect(db.query.magicLinks.findMany).toHaveBeenCalledWith(
+				expect.objectContaining({ offset: 5 })
+			);
+			const { sql, params } = renderWhere(where.mock.calls[0][0]);
+			expect(sql).toMatch(/"used" = \$2/);
+			expect(sql).toMatch(/"token_hash" in \(\$3, \$4\)/);
+			expect(params).toEqual(['user@example.com', false, 'old1', 'old2']);
+		});
+
+		describe('configured pending cap', () => {
+			afterEach(() => {
+				delete mockEnv.MAGIC_LINK_MAX_PENDING;
+				vi.resetModules();
+			});
+
+			it('should use the configured pending link cap', async () => {
+				mockEnv.MAGIC_LINK_MAX_PENDING = '2';
+				vi.resetModules();
+				const { logMagicLink: freshLogMagicLink } = await import('../users');
+				const { db: freshDb } = await import('$lib/db/client');
+				vi.mocked(freshDb.insert).mockReturnValue({
+					values: vi.fn().mockReturnValue({ returning: vi.fn().mockResolvedValue([]) })
+				} as any);
+				vi.mocked(freshDb.query.magicLinks.findMany).mockResolvedValue([]);
+
+				await freshLogMagicLink({
+					identifier: 'user@example.com',
+					tokenHash: 'hash123456',
+					expiresAt: new Date()
+				});
+
+				expect(freshDb.query.magicLinks.findMany).toHaveBeenCalledWith(
+					expect.objectContaining({ offset: 2 })
+				);
+			});
+		});
+	});
+
+	describe('isMagicLinkValid', () => {
+		it('should return true for a pending link without marking it used', async () => {
+			vi.mocked(db.query.magicLinks.findFirst).mockResolvedValue({
+				identifier: 'user@example.com',
+				tokenHash: 'hash123456',
+				expiresAt: new Date(Date.now() + 15 * 60 * 1000),
+				used: false
+			});
+
+			const result = await isMagicLinkValid('user@example.com', 'hash123456');
+
+			expect(result).toBe(true);
+			expect(db.update).not.toHaveBeenCalled();
+		});
+
+		it('should return false for a used, expired or unknown link', async () => {
+			vi.mocked(db.query.magicLinks.findFirst).mockResolvedValue(undefined);
+
+			const result = await isMagicLinkValid('user@example.com', 'hash123456');
+
+			expect(result).toBe(false);
+		});
+
+		it('should check the same pending, unexpired predicate that consuming uses', async () => {
+			vi.useFakeTimers();
+			vi.setSystemTime(new Date('2026-01-01T00:00:00Z'));
+			vi.mocked(db.query.magicLinks.findFirst).mockResolvedValue(undefined);
+
+			await isMagicLinkValid('user@example.com', 'hash123456');
+			vi.useRealTimers();
+
+			const [{ where }] = vi.mocked(db.query.magicLinks.findFirst).mock.calls[0] as any;
+			const { sql, params } = renderWhere(where);
+			expect(sql).toMatch(/"used" = \$3/);
+			expect(sql).toMatch(/"expires_at" > \$4/);
+			expect(params.slice(0, 3)).toEqual(['user@example.com', 'hash123456', false]);
+			expect(new Date(params[3] as string).getTime()).toBe(
+				new Date('2025-12-31T23:59:55Z').getTime()
+			);
+		});
+	});
+
+	describe('consumeMagicLink', () => {
+		const link = {
+			identifier: 'user@example.com',
+			tokenHash: 'hash123456',
+			expiresAt: new Date(Date.now() + 15 * 60 * 1000),
+			used: true
+		};
+
+		it('should mark the link used and return it', async () => {
+			const set = vi.fn().mockReturnValue({
+				where: vi.fn().mockReturnValue({
+					returning: vi.fn().mockResolvedValue([link])
+				})
+			});
+			vi.mocked(db.update).mockReturnValue({ set } as any);
+
+			const result = await consumeMagicLink('user@example.com', 'hash123456');
+
+			expect(result).toEqual(link);
+			expect(set).toHaveBeenCalledWith({ used: true });
+		});
+
+		it('should return undefined for a used, expired or unknown link', async () => {
+			vi.mocked(db.update).mockReturnValue({
+				set: vi.fn().mockReturnValue({
+					where: vi.fn().mockReturnValue({
+						returning: vi.fn().mockResolvedValue([])
+					})
+				})
+			} as any);
+
+			const result = await consumeMagicLink('user@example.com', 'hash123456');
+
+			expect(result).toBeUndefined();
+		});
+
+		it('should only update a pending, unexpired link', async () => {
+			vi.useFakeTimers();
+			vi.setSystemTime(new Date('2026-01-01T00:00:00Z'));
+			const where = vi.fn().mockReturnValue({
+				returning: vi.fn().mockResolvedValue([link])
+			});
+			vi.mocked(db.update).mockReturnValue({
+				set: vi.fn().mockReturnValue({ where })
+			} as any);
+
+			await consumeMagicLink('user@example.com', 'hash123456');
+			vi.useRealTimers();
+
+			const { sql, params } = renderWhere(where.mock.calls[0][0]);
+			expect(sql).toMatch(/"identifier" = \$1/);
+			expect(sql).toMatch(/"token_hash" = \$2/);
+			expect(sql).toMatch(/"used" = \$3/);
+			expect(sql).toMatch(/"expires_at" > \$4/);
+			expect(params.slice(0, 3)).toEqual(['user@example.com', 'hash123456', false]);
+			// The cutoff sits the default 5s leeway before now
+			expect(new Date(params[3] as string).getTime()).toBe(
+				new Date('2025-12-31T23:59:55Z').getTime()
+			);
+		});
+	});
+
+	describe('deleteMagicLinksForIdentifier', () => {
+		it('should delete pending links for the identifier', async () => {
+			const deletedLinks = [
+				{
+					identifier: 'user@example.com',
+					tokenHash: 'hash1',
+					expiresAt: new Date(Date.now() + 15 * 60 * 1000),
+					used: false
+				}
+			];
+
+			vi.mocked(db.delete).mockReturnValue({
+				where: vi.fn().mockReturnValue({
+					returning: vi.fn().mockResolvedValue(deletedLinks)
+				})
+			} as any);
+
+			const result = await deleteMagicLinksForIdentifier('user@example.com');
+
+			expect(result).toEqual(deletedLinks);
+		});
+
+		it('should succeed when no link exists', async () => {
+			vi.mocked(db.delete).mockReturnValue({
+				where: vi.fn().mockReturnValue({
+					returning: vi.fn().mockResolvedValue([])
+				})
+			} as any);
+
+			const result = await deleteMagicLinksForIdentifier('unknown@example.com');
+
+			expect(result).toEqual([]);
+		});
+	});
+
+	describe('magic link identifiers', () => {
+		// Minimal stand-in for the magic_links table that applies the rendered predicates
+		function useLinkStore(links: { identifier: string; tokenHash: string; used: boolean }[]) {
+			vi.mocked(db.delete).mockReturnValue({
+				where: vi.fn((predicate: SQL) => {
+					const [identifier] = renderWhere(predicate).params;
+					const cancelled = links.filter((l) => l.identifier === identifier && !l.used);
+					links.splice(0, links.length, ...links.filter((l) => !cancelled.includes(l)));
+					return { returning: vi.fn().mockResolvedValue(cancelled) };
+				})
+			} as any);
+			vi.mocked(db.update).mockReturnValue({
+				set: vi.fn().mockReturnValue({
+					where: vi.fn((predicate: SQL) => {
+						const [identifier, tokenHash] = renderWhere(predicate).params;
+						const link = links.find(
+							(l) => l.identifier === identifier && l.tokenHash === tokenHash && !l.used
+						);
+						return { returning: vi.fn().mockResolvedValue(link ? [link] : []) };
+					})
+				})
+			} as any);
+		}
+
+		it('should store email identifiers normalized', async () => {
+			const values = vi.fn().mockReturnValue({ returning: vi.fn().mockResolvedValue([]) });
+			vi.mocked(db.insert).mockReturnValue({ values } as any);
+			vi.mocked(db.query.magicLinks.findMany).mockResolvedValue([]);
+
+			await logMagicLink({
+				identifier: ' User@Example.COM ',
+				tokenHash: 'hash123456',
+				expiresAt: new Date()
+			});
+
+			expect(values).toHaveBeenCalledWith(
+				expect.objectContaining({ identifier: 'user@example.com' })
+			);
+		});
+
+		it('should leave phone identifiers as entered, apart from whitespace', async () => {
+			vi.mocked(db.query.magicLinks.findFirst).mockResolvedValue(undefined);
+
+			await isMagicLinkValid(' +15551234567 ', 'hash123456');
+
+			const [{ where }] = vi.mocked(db.query.magicLinks.findFirst).mock.calls[0] as any;
+			expect(renderWhere(where).params[0]).toBe('+15551234567');
+		});
+
+		it('should not consume a token cancelled under a different casing', async () => {
+			useLinkStore([{ identifier: 'user@example.com', tokenHash: 'hash123456', used: false }]);
+
+			const cancelled = await deleteMagicLinksForIdentifier('User@Example.com');
+			const consumed = await consumeMagicLink('USER@example.com', 'hash123456');
+
+			expect(cancelled).toHaveLength(1);
+			expect(consumed).toBeUndefined();
 		});
 	});
 
@@ -1,11 +1,56 @@
 // noinspection UnnecessaryLocalVariableJS
 
 import { db } from '$lib/db/client';
-import { users, jwtTokenLogs, magicLinks } from '$lib/db';
-import { eq, lt } from 'drizzle-orm';
+import { users, userIdentities, passkeys, jwtTokenLogs, magicLinks } from '$lib/db';
+import { and, desc, eq, gt, inArray, lt } from 'drizzle-orm';
+import { deriveDisplayName } from '$lib/utils/names';
+import { parseEmail, type Email } from '$lib/utils/email.server';
+import { env } from '$env/dynamic/private';
+import { isUserPlan } from '$lib/billing/plans';
+import { expiryCutoff } from '$lib/utils/expiry.server';
+
+// Thrown when an email is already taken by another user; client-correctable, so maps to 409
+export class EmailInUseError extends Error {
+	readonly status = 409;
+	readonly code = 'email_in_use';
+
+	constructor(email: string) {
+		super(`User with email ${email} already exists`);
+		this.name = 'EmailInUseError';
+	}
+}
+
+// Thrown when a provider identity is already linked to a different user
+export class IdentityInUseError extends Error {
+	readonly status = 409;
+	readonly code = 'identity_in_use';
+
+	constructor(provider: string) {
+		super(`This ${provider} account is already linked to another user`);
+		this.name = 'IdentityInUseError';
+	}
+}
+
+// Plan assigned to new signups; unknown values fall back to FreeTrial
+const DEFAULT_NEW_USER_PLAN = isUserPlan(env.DEFAULT_NEW_USER_PLAN)
+	? env.DEFAULT_NEW_USER_PLAN
+	: 'FreeTrial';
+
+if (env.DEFAULT_NEW_USER_PLAN && DEFAULT_NEW_USER_PLAN !== env.DEFAULT_NEW_USER_PLAN) {
+	console.warn(
+		`Unknown DEFAULT_NEW_USER_PLAN "${env.DEFAULT_NEW_USER_PLAN}", falling back to FreeTrial`
+	);
+}
+
+// Check for a Postgres unique violation on users.email, unwrapping Drizzle's query error
+function isEmailUniqueViolation(error: unknown): boolean {
+	const cause = (error as { cause?: unknown } | null)?.cause ?? error;
+	const { code, constraint } = (cause ?? {}) as { code?: string; constraint?: string };
+	return code === '23505' && (!constraint || constraint === 'users_email_unique');
+}
 
 // Check if a user exists by email
-export async function userExistsByEmail(email: string): Promise<boolean> {
+export async function userExistsByEmail(email: Email): Promise<boolean> {
 	const user = await db.query.users.findFirst({
 		where: eq(users.email, email)
 	});
@@ -20,18 +65,29 @@ export async function userExistsByPhone(phone: string): Promise<boolean> {
 	return !!user;
 }
 
+// Find many users by id in a single query; unknown ids are skipped
+export async function findUsersByIds(userIds: string[]) {
+	if (userIds.length === 0) {
+		return [];
+	}
+
+	return db.query.users.findMany({
+		where: inArray(users.id, [...new Set(userIds)])
+	});
+}
+
 // Create a user
 export async function createUser(userData: {
 	id?: string;
 	name?: string;
-	email?: string;
+	email?: Email;
 	phone?: string;
 }) {
 	// Check if user with this email already exists
 	if (userData.email) {
 		const emailExists = await userExistsByEmail(userData.email);
 		if (emailExists) {
-			throw new Error(`User with email ${userData.email} already exists`);
+			throw new EmailInUseError(userData.email);
 		}
 	}
 
@@ -43,8 +99,64 @@ export async function createUser(userData: {
 		}
 	}
 
-	const newUser = await db.insert(users).values(userData).returning();
-	return newUser[0];
+	// Fall back to a name derived from the email; users can change it later
+	const name = userData.name ?? (userData.email ? deriveDisplayName(userData.email) : undefined);
+
+	// A concurrent signup can pass the check above; the unique constraint still catches it
+	try {
+		const newUser = await db
+			.insert(users)
+			.values({ ...userData, name, plan: DEFAULT_NEW_USER_PLAN })
+			.returning();
+		return newUser[0];
+	} catch (error) {
+		if (userData.email && isEmailUniqueViolation(error)) {
+			throw new EmailInUseError(userData.email);
+		}
+		throw error;
+	}
+}
+
+// Create a user or return the existing one with the same email, in a single statement
+export async function upsertUserByEmail(userData: { email: Email; name?: string }) {
+	const user = await db
+		.insert(users)
+		.values({
+			...userData,
+			name: userData.name ?? deriveDisplayName(userData.email),
+			plan: DEFAULT_NEW_USER_PLAN
+		})
+		.onConflictDoUpdate({
+			target: users.email,
+			set: { updatedAt: new Date() }
+		})
+		.returning();
+	return user[0];
+}
+
+// Link a provider identity or return the existing link, in a single statement
+// Only a link owned by the same user is updated; one owned by someone else is rejected
+export async function upsertIdentity(identityData: {
+	userId: string;
+	provider: string;
+	providerId: string;
+	email: Email;
+}) {
+	const identity = await db
+		.insert(userIdentities)
+		.values(identityData)
+		.onConflictDoUpdate({
+			target: [userIdentities.provider, userIdentities.providerId],
+			set: { email: identityData.email },
+			setWhere: eq(userIdentities.userId, identityData.userId)
+		})
+		.returning();
+
+	// The conflicting row failed setWhere, so it belongs to another user
+	if (!identity[0]) {
+		throw new IdentityInUseError(identityData.provider);
+	}
+	return identity[0];
 }
 
 // Delete a user
@@ -53,6 +165,16 @@ export async function deleteUser(userId: string) {
 	return deletedUser[0];
 }
 
+// Preview what deleting a user would remove (identities and passkeys cascade)
+export async function previewUserDeletion(userId: string) {
+	const [identities, userPasskeys] = await Promise.all([
+		db.$count(userIdentities, eq(userIdentities.userId, userId)),
+		db.$count(passkeys, eq(passkeys.userId, userId))
+	]);
+
+	return { identities, passkeys: userPasskeys };
+}
+
 // Log a JWT services
 export async function logJWTToken(tokenData: {
 	userId: string;
@@ -80,19 +202,93 @@ export async function expireOldJWTLogs() {
 	return deletedLogs;
 }
 
-// Log a magic link
+// Magic links are keyed by email or phone; emails are normalized like user emails so that
+// issuing, checking and cancelling a link agree on the identifier regardless of casing
+function normalizeIdentifier(identifier: string) {
+	return parseEmail(identifier) ?? identifier.trim();
+}
+
+// Pending links kept per identifier; set MAGIC_LINK_MAX_PENDING to change, defaults to 5
+const MAX_PENDING_MAGIC_LINKS = parseMaxPending(env.MAGIC_LINK_MAX_PENDING);
+
+function parseMaxPending(value: string | undefined): number {
+	const limit = Number(value);
+	return Number.isInteger(limit) && limit > 0 ? limit : 5;
+}
+
+// Log a magic link, dropping the oldest unused links for the identifier beyond the cap
 export async function logMagicLink(linkData: {
 	identifier: string;
 	tokenHash: string;
 	expiresAt: Date;
 }) {
+	const identifier = normalizeIdentifier(linkData.identifier);
+	const pending = and(eq(magicLinks.identifier, identifier), eq(magicLinks.used, false));
+
 	const logEntry = await db
 		.insert(magicLinks)
-		.values(linkData)
+		.values({ ...linkData, identifier })
 		.returning();
+
+	// Every link gets the same lifetime, so the latest expiry is the most recently issued
+	const staleLinks = await db.query.magicLinks.findMany({
+		columns: { tokenHash: true },
+		where: pending,
+		orderBy: desc(magicLinks.expiresAt),
+		offset: MAX_PENDING_MAGIC_LINKS
+	});
+
+	if (staleLinks.length > 0) {
+		const staleHashes = staleLinks.map((link) => link.tokenHash);
+		await db.delete(magicLinks).where(and(pending, inArray(magicLinks.tokenHash, staleHashes)));
+	}
+
 	return logEntry[0];
 }
 
+// Check a magic link is unused and unexpired without consuming it
+export async function isMagicLinkValid(identifier: string, tokenHash: string): Promise<boolean> {
+	const link = await db.query.magicLinks.findFirst({
+		where: and(
+			eq(magicLinks.identifier, normalizeIdentifier(identifier)),
+			eq(magicLinks.tokenHash, tokenHash),
+			eq(magicLinks.used, false),
+			gt(magicLinks.expiresAt, expiryCutoff())
+		)
+	});
+	return !!link;
+}
+
+// Mark a magic link as used, returning it only if it was unused and unexpired
+// The check and update happen in one statement so concurrent verifications can't both succeed
+export async function consumeMagicLink(identifier: string, tokenHash: string) {
+	const consumedLinks = await db
+		.update(magicLinks)
+		.set({ used: true })
+		.where(
+			and(
+				eq(magicLinks.identifier, normalizeIdentifier(identifier)),
+				eq(magicLinks.tokenHash, tokenHash),
+				eq(magicLinks.used, false),
+				gt(magicLinks.expiresAt, expiryCutoff())
+			)
+		)
+		.returning();
+	return consumedLinks[0];
+}
+
+// Cancel all pending magic links for an identifier
+export async function deleteMagicLinksForIdentifier(identifier: string) {
+	const deletedLinks = await db
+		.delete(magicLinks)
+		.where(
+			and(eq(magicLinks.identifier, normalizeIdentifier(identifier)), eq(magicLinks.used, false))
+		)
+		.returning();
+
+	return deletedLinks;
+}
+
 // Expire magic links after specified time
 export async function expireOldMagicLinks(expirationDate?: Date) {
 	const cutoffDate = expirationDate || new Date();
@@ -11,6 +11,7 @@ import {
 	pgEnum
 } from 'drizzle-orm/pg-core';
 import { sql } from 'drizzle-orm';
+import type { Email } from '$lib/utils/email.server';
 
 export const planEnum = pgEnum('plan', ['FreeTrial', 'Plus', 'Premium', 'Lifetime']);
 
@@ -19,7 +20,7 @@ export const users = pgTable(
 	{
 		id: uuid('id').defaultRandom().primaryKey(),
 		name: text('name'),
-		email: text('email').unique(),
+		email: text('email').unique().$type<Email>(),
 		phone: text('phone'),
 		plan: planEnum('plan').notNull().default('FreeTrial'),
 		onboardingCompleted: boolean('onboarding_completed').notNull().default(false),
@@ -44,7 +45,7 @@ export const userIdentities = pgTable(
 			.references(() => users.id, { onDelete: 'cascade' }),
 		provider: text('provider').notNull(),
 		providerId: text('provider_id').notNull(),
-		email: text('email').notNull(),
+		email: text('email').notNull().$type<Email>(),
 		createdAt: timestamp('created_at').notNull().defaultNow()
 	},
 	(table) => [
@@ -85,6 +86,9 @@ export const magicLinks = pgTable(
 			name: "magic_links_pkey",
 			columns: [table.identifier, table.tokenHash],
 		}),
+		// Link lookups already use the primary key; this only backs the range delete in
+		// expireOldMagicLinks, which would otherwise scan the whole table
+		index("idx_magic_links_expires_at").on(table.expiresAt),
 	]
 );
 
@@ -1,3 +1,4 @@
 export * as auth from './auth';
+export * as billing from './billing';
 export * as db from './db';
 export * as ui from './ui';
@@ -0,0 +1,45 @@
+import { describe, it, expect, vi } from 'vitest';
+import { parseEmail } from '../email.server';
+
+vi.mock('$env/dynamic/private', () => ({
+	env: {}
+}));
+
+describe('parseEmail', () => {
+	it('should lowercase and trim the address', () => {
+		expect(parseEmail('  John.Doe@Example.COM ')).toBe('john.doe@example.com');
+	});
+
+	it('should produce the same value for equivalent inputs', () => {
+		expect(parseEmail('USER@example.com')).toBe(parseEmail('user@example.com '));
+	});
+
+	it('should reject input without an @', () => {
+		expect(parseEmail('user.example.com')).toBeNull();
+	});
+
+	it('should reject input without a domain dot', () => {
+		expect(parseEmail('user@localhost')).toBeNull();
+	});
+
+	it('should reject embedded whitespace', () => {
+		expect(parseEmail('us er@example.com')).toBeNull();
+	});
+
+	it('should reject empty and overly long input', () => {
+		expect(parseEmail('   ')).toBeNull();
+		expect(parseEmail(`${'a'.repeat(250)}@example.com`)).toBeNull();
+	});
+
+	describe('local part casing', () => {
+		it('should lowercase the whole address by default', () => {
+			expect(parseEmail('John.Doe@Example.COM')).toBe('john.doe@example.com');
+		});
+
+		it('should only lowercase the domain when configured', () => {
+			expect(parseEmail('John.Doe@Example.COM', { lowercaseLocalPart: false })).toBe(
+				'John.Doe@example.com'
+			);
+		});
+	});
+});
@@ -0,0 +1,22 @@
+import { describe, it, expect } from 'vitest';
+import { parseBooleanEnv } from '../env';
+
+describe('parseBooleanEnv', () => {
+	it('should use the fallback when unset or blank', () => {
+		expect(parseBooleanEnv(undefined, true)).toBe(true);
+		expect(parseBooleanEnv(undefined, false)).toBe(false);
+		expect(parseBooleanEnv('  ', false)).toBe(false);
+	});
+
+	it('should treat false, 0, off and no as false in any case', () => {
+		for (const value of ['false', '0', 'off', 'no', ' FALSE ', 'Off', 'NO']) {
+			expect(parseBooleanEnv(value, true)).toBe(false);
+		}
+	});
+
+	it('should treat any other value as true', () => {
+		for (const value of ['true', '1', 'on', 'yes', 'TRUE']) {
+			expect(parseBooleanEnv(value, false)).toBe(true);
+		}
+	});
+});
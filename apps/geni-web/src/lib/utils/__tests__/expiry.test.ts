@@ -0,0 +1,35 @@
+import { describe, it, expect, vi } from 'vitest';
+import { parseLeeway, expiryCutoff, EXPIRY_LEEWAY_SECONDS } from '../expiry.server';
+
+vi.mock('$env/dynamic/private', () => ({
+	env: {}
+}));
+
+describe('parseLeeway', () => {
+	it('should default to 5 seconds when unset', () => {
+		expect(parseLeeway(undefined)).toBe(5);
+	});
+
+	it('should treat a blank value as unset', () => {
+		expect(parseLeeway('')).toBe(5);
+		expect(parseLeeway('  ')).toBe(5);
+	});
+
+	it('should accept explicit values, including zero', () => {
+		expect(parseLeeway('30')).toBe(30);
+		expect(parseLeeway('0')).toBe(0);
+	});
+
+	it('should fall back for invalid values', () => {
+		expect(parseLeeway('-1')).toBe(5);
+		expect(parseLeeway('soon')).toBe(5);
+	});
+});
+
+describe('expiryCutoff', () => {
+	it('should subtract the leeway from now', () => {
+		const now = new Date('2025-01-01T00:00:10Z');
+
+		expect(expiryCutoff(now)).toEqual(new Date(now.getTime() - EXPIRY_LEEWAY_SECONDS * 1000));
+	});
+});
@@ -0,0 +1,29 @@
+import { describe, it, expect } from 'vitest';
+import { deriveDisplayName } from '../names';
+
+describe('deriveDisplayName', () => {
+	it('should title-case dot-separated local parts', () => {
+		expect(deriveDisplayName('john.doe@example.com')).toBe('John Doe');
+	});
+
+	it('should strip plus-addressing', () => {
+		expect(deriveDisplayName('john.doe+test@example.com')).toBe('John Doe');
+	});
+
+	it('should treat underscores and hyphens as separators', () => {
+		expect(deriveDisplayName('mary_jane-watson@example.com')).toBe('Mary Jane Watson');
+	});
+
+	it('should normalize casing', () => {
+		expect(deriveDisplayName('JOHN.DOE@example.com')).toBe('John Doe');
+	});
+
+	it('should collapse repeated separators', () => {
+		expect(deriveDisplayName('john..doe__@example.com')).toBe('John Doe');
+	});
+
+	it('should return undefined when nothing usable remains', () => {
+		expect(deriveDisplayName('+tag@example.com')).toBeUndefined();
+		expect(deriveDisplayName('')).toBeUndefined();
+	});
+});
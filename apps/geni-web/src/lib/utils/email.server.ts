@@ -0,0 +1,56 @@
+import { env } from '$env/dynamic/private';
+import { parseBooleanEnv } from '$lib/utils/env';
+
+declare const EmailBrand: unique symbol;
+
+/**
+ * A trimmed, case-normalized and validated email address
+ * Only obtainable through parseEmail, so normalization can't be skipped
+ */
+export type Email = string & { readonly [EmailBrand]: true };
+
+// Deliberately loose: one @, no whitespace, a dot in the domain
+const EMAIL_PATTERN = /^[^\s@]+@[^\s@]+\.[^\s@]+$/;
+const MAX_EMAIL_LENGTH = 254;
+
+export interface EmailNormalizationOptions {
+	/**
+	 * Lowercase the local part too. Local parts are case-sensitive per RFC 5321, but virtually
+	 * every provider treats them as insensitive, so this defaults to true. The domain is always
+	 * lowercased.
+	 */
+	lowercaseLocalPart?: boolean;
+}
+
+// Email case policy; set EMAIL_LOWERCASE_LOCAL_PART=false to preserve local-part casing
+// Lookups compare against the stored column exactly, so rows written before normalization, or
+// while this flag had a different value, won't match. Run queries/lowercase_user_emails.sql after
+// deploying or when setting the flag back to true; setting it to false has no backfill, since a
+// lowercased local part can't be restored
+const EMAIL_OPTIONS: EmailNormalizationOptions = {
+	lowercaseLocalPart: parseBooleanEnv(env.EMAIL_LOWERCASE_LOCAL_PART, true)
+};
+
+/**
+ * Normalizes and validates an email address
+ * @param value - Raw email input
+ * @param options - Normalization policy (defaults to the configured one)
+ * @returns Canonical Email, or null if the input is not a valid address
+ */
+export function parseEmail(
+	value: string,
+	options: EmailNormalizationOptions = EMAIL_OPTIONS
+): Email | null {
+	const { lowercaseLocalPart = true } = options;
+	const trimmed = value.trim();
+
+	if (trimmed.length > MAX_EMAIL_LENGTH || !EMAIL_PATTERN.test(trimmed)) {
+		return null;
+	}
+
+	const at = trimmed.lastIndexOf('@');
+	const localPart = trimmed.slice(0, at);
+	const domain = trimmed.slice(at + 1).toLowerCase();
+
+	return `${lowercaseLocalPart ? localPart.toLowerCase() : localPart}@${domain}` as Email;
+}
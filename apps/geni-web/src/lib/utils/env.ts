@@ -0,0 +1,15 @@
+// Values that switch a boolean setting off, compared trimmed and case-insensitively
+const FALSE_VALUES = ['false', '0', 'off', 'no'];
+
+/**
+ * Parse a boolean environment variable
+ * @param value - Raw value (e.g. from SECURE_COOKIES)
+ * @param fallback - Result when the variable is unset or blank
+ * @returns False for "false", "0", "off" or "no" in any case, true for anything else
+ */
+export function parseBooleanEnv(value: string | undefined, fallback: boolean): boolean {
+	if (value === undefined || value.trim() === '') {
+		return fallback;
+	}
+	return !FALSE_VALUES.includes(value.trim().toLowerCase());
+}
@@ -0,0 +1,30 @@
+import { env } from '$env/dynamic/private';
+
+const DEFAULT_EXPIRY_LEEWAY_SECONDS = 5;
+
+/**
+ * Parse an expiry leeway in seconds
+ * @param value - Raw value (e.g. from EXPIRY_LEEWAY_SECS)
+ * @returns Seconds, or the 5 second default for missing, blank, negative or non-numeric values
+ */
+export function parseLeeway(value: string | undefined): number {
+	if (value === undefined || value.trim() === '') {
+		return DEFAULT_EXPIRY_LEEWAY_SECONDS;
+	}
+
+	const seconds = Number(value);
+	return Number.isFinite(seconds) && seconds >= 0 ? seconds : DEFAULT_EXPIRY_LEEWAY_SECONDS;
+}
+
+// Tolerated clock skew between the node that issues a token and the one that checks it,
+// applied to every JWT and magic-link expiry check
+export const EXPIRY_LEEWAY_SECONDS = parseLeeway(env.EXPIRY_LEEWAY_SECS);
+
+/**
+ * Earliest expiry time still treated as valid
+ * @param now - Current time
+ * @returns now minus the leeway; anything expiring after this is not yet expired
+ */
+export function expiryCutoff(now: Date = new Date()): Date {
+	return new Date(now.getTime() - EXPIRY_LEEWAY_SECONDS * 1000);
+}
@@ -0,0 +1,18 @@
+/**
+ * Derives a readable display name from an email address
+ * @param email - Email address (e.g. "john.doe+test@example.com")
+ * @returns Title-cased name from the local part (e.g. "John Doe"), or undefined if none remains
+ */
+export function deriveDisplayName(email: string): string | undefined {
+	const localPart = email.trim().split('@')[0] ?? '';
+
+	// Drop plus-addressing tags before splitting on separators
+	const name = localPart
+		.split('+')[0]
+		.split(/[._-]+/)
+		.filter(Boolean)
+		.map((word) => word.charAt(0).toUpperCase() + word.slice(1).toLowerCase())
+		.join(' ');
+
+	return name || undefined;
+}
@@ -0,0 +1,8 @@
+import { json } from '@sveltejs/kit';
+import { getFeatureFlags } from '$lib/auth/services/features.server';
+import type { RequestHandler } from './$types';
+
+// Expose enabled sign-in methods so the frontend knows what to render
+export const GET: RequestHandler = () => {
+	return json(getFeatureFlags());
+};
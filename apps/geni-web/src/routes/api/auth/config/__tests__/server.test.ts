@@ -0,0 +1,33 @@
+import { describe, it, expect, afterEach, vi } from 'vitest';
+import { GET } from '../+server';
+
+const mockEnv = vi.hoisted(() => ({}) as Record<string, string | undefined>);
+
+vi.mock('$env/dynamic/private', () => ({
+	env: mockEnv
+}));
+
+describe('GET /api/auth/config', () => {
+	afterEach(() => {
+		delete mockEnv.ENABLE_OAUTH;
+	});
+
+	it('should report every method enabled by default', async () => {
+		const response = await GET({} as any);
+
+		expect(await response.json()).toEqual({
+			magicLink: true,
+			phoneOtp: true,
+			passkeys: true,
+			oauth: true
+		});
+	});
+
+	it('should report a disabled method as disabled', async () => {
+		mockEnv.ENABLE_OAUTH = 'false';
+
+		const response = await GET({} as any);
+
+		expect(await response.json()).toMatchObject({ oauth: false, magicLink: true });
+	});
+});
@@ -0,0 +1,31 @@
+-- Backfill: normalize stored emails to the form the app now writes (see normalizeEmail)
+-- Lookups and the users.email unique constraint compare exactly, so rows written before
+-- normalization won't match a lowercased sign-in until this has run.
+-- Assumes EMAIL_LOWERCASE_LOCAL_PART is unset or true.
+
+BEGIN;
+
+-- Addresses that would collide once lowercased; these need merging by hand and are skipped below
+SELECT lower(trim(email)) AS normalized_email, array_agg(id) AS user_ids
+FROM users
+WHERE email IS NOT NULL
+GROUP BY lower(trim(email))
+HAVING count(*) > 1;
+
+UPDATE users
+SET email = lower(trim(email)), updated_at = now()
+WHERE email IS NOT NULL
+	AND email <> lower(trim(email))
+	AND lower(trim(email)) IN (
+		SELECT lower(trim(email))
+		FROM users
+		WHERE email IS NOT NULL
+		GROUP BY lower(trim(email))
+		HAVING count(*) = 1
+	);
+
+UPDATE user_identities
+SET email = lower(trim(email))
+WHERE email <> lower(trim(email));
+
+COMMIT;